@@ -4,6 +4,7 @@
 use clap::{App, AppSettings, load_yaml};
 
 mod precmd;
+mod scan;
 
 fn main() {
     // Load our CLI args from the yaml file
@@ -15,6 +16,7 @@ fn main() {
     // Print the line corresponding to the subcommand
     match matches.subcommand() {
         ("precmd", Some(sub_matchings)) => precmd::render(sub_matchings),
+        ("scan", Some(sub_matchings)) => scan::render(sub_matchings),
         // ("prompt", Some(sub_matchings)) => prompt::render(sub_matchings),
         _ => (),
     }
@@ -0,0 +1,275 @@
+use dirs;
+use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How long a successfully detected toolchain version is trusted before `detect` re-invokes
+/// the toolchain's `--version` subprocess. Toolchain versions essentially never change
+/// mid-session, but `detect` runs on every single prompt render, so shelling out each time
+/// would make the prompt noticeably slower than the rest of pursue.
+const CACHE_TTL_SECS: u64 = 300;
+
+/// Per-module toolchain version cache, persisted as JSON under the user's cache directory so
+/// it survives across shell invocations (each prompt render is a fresh process).
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct VersionCache {
+    #[serde(flatten)]
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct CacheEntry {
+    version_output: String,
+    cached_at: u64,
+}
+
+fn cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("pursue").join("languages.json"))
+}
+
+fn load_cache() -> VersionCache {
+    cache_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &VersionCache) {
+    let path = match cache_path() {
+        Some(path) => path,
+        None => return,
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(contents) = serde_json::to_string(cache) {
+        let _ = fs::write(path, contents);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Returns the cached `--version` output for `module` if it's still within `CACHE_TTL_SECS`.
+fn cached_version_output(cache: &VersionCache, module: &LanguageModule) -> Option<String> {
+    let entry = cache.entries.get(module.name)?;
+    if now_secs().saturating_sub(entry.cached_at) < CACHE_TTL_SECS {
+        Some(entry.version_output.clone())
+    } else {
+        None
+    }
+}
+
+/// A detector for one language/runtime: looks for a marker file in the working directory
+/// and, if found, shells out to the toolchain to extract its version.
+///
+/// New languages are added by appending a [`LanguageModule`] to [`LANGUAGE_MODULES`]; the
+/// marker-file and version-parsing machinery is shared.
+struct LanguageModule {
+    /// Config key and segment name, e.g. `"node"`.
+    name: &'static str,
+    /// Printed immediately before the version, e.g. `"⬢ "`.
+    symbol: &'static str,
+    /// Any of these present in the working directory triggers detection. A leading `*.`
+    /// matches any file with that extension instead of a literal filename.
+    marker_files: &'static [&'static str],
+    /// The command that prints the toolchain's version (first element is the binary).
+    command: &'static [&'static str],
+}
+
+const LANGUAGE_MODULES: &[LanguageModule] = &[
+    LanguageModule {
+        name: "node",
+        symbol: "⬢ ",
+        marker_files: &["package.json"],
+        command: &["node", "--version"],
+    },
+    LanguageModule {
+        name: "python",
+        symbol: "🐍 ",
+        marker_files: &["*.py", "requirements.txt", "pyproject.toml"],
+        command: &["python", "--version"],
+    },
+    LanguageModule {
+        name: "rust",
+        symbol: "🦀 ",
+        marker_files: &["Cargo.toml"],
+        command: &["rustc", "--version"],
+    },
+];
+
+/// Runs every enabled language module against `cwd`, returning `symbol + version` for each
+/// one whose marker file is present and whose toolchain is on `PATH`.
+///
+/// `enabled` filters which modules run and in what order; an empty list runs all of the
+/// built-in modules in their declared order.
+crate fn detect(cwd: &Path, enabled: &[String]) -> Vec<String> {
+    let modules: Vec<&LanguageModule> = if enabled.is_empty() {
+        LANGUAGE_MODULES.iter().collect()
+    } else {
+        enabled
+            .iter()
+            .filter_map(|name| LANGUAGE_MODULES.iter().find(|module| module.name == name))
+            .collect()
+    };
+
+    let mut cache = load_cache();
+    let mut cache_dirty = false;
+
+    let results = modules
+        .into_iter()
+        .filter(|module| has_marker_file(cwd, module.marker_files))
+        .filter_map(|module| {
+            let version_output = match cached_version_output(&cache, module) {
+                Some(version_output) => version_output,
+                None => {
+                    let version_output = run_version_command(module.command)?;
+                    cache.entries.insert(
+                        module.name.to_string(),
+                        CacheEntry {
+                            version_output: version_output.clone(),
+                            cached_at: now_secs(),
+                        },
+                    );
+                    cache_dirty = true;
+                    version_output
+                }
+            };
+            let version = major_minor_version(&version_output)?;
+            Some(format!("{}{}", module.symbol, version))
+        })
+        .collect();
+
+    if cache_dirty {
+        save_cache(&cache);
+    }
+
+    results
+}
+
+fn has_marker_file(cwd: &Path, markers: &[&str]) -> bool {
+    markers.iter().any(|marker| match marker.strip_prefix("*.") {
+        Some(extension) => has_file_with_extension(cwd, extension),
+        None => cwd.join(marker).exists(),
+    })
+}
+
+fn has_file_with_extension(dir: &Path, extension: &str) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries.filter_map(Result::ok).any(|entry| {
+                entry.path().extension().and_then(|ext| ext.to_str()) == Some(extension)
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Runs `command`, returning whichever of stdout/stderr the toolchain printed its version
+/// to (some older toolchains write `--version` output to stderr).
+fn run_version_command(command: &[&str]) -> Option<String> {
+    let (binary, args) = command.split_first()?;
+    let output = Command::new(binary).args(args).output().ok()?;
+    let text = if output.stdout.is_empty() {
+        output.stderr
+    } else {
+        output.stdout
+    };
+    String::from_utf8(text).ok()
+}
+
+/// Pulls the first `major.minor` version number out of a toolchain's `--version` output,
+/// keeping a leading `v` if the toolchain prints one (e.g. node's `v18.2.0`).
+fn major_minor_version(version_output: &str) -> Option<String> {
+    let captures = Regex::new(r"(v)?(\d+)\.(\d+)").unwrap().captures(version_output)?;
+    let prefix = captures.get(1).map_or("", |m| m.as_str());
+    Some(format!("{}{}.{}", prefix, &captures[2], &captures[3]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn major_minor_version_strips_the_patch_number() {
+        assert_eq!(major_minor_version("rustc 1.72.0 (abcdef 2023-01-01)").unwrap(), "1.72");
+        assert_eq!(major_minor_version("Python 3.11.4"), Some(String::from("3.11")));
+    }
+
+    #[test]
+    fn major_minor_version_keeps_a_leading_v() {
+        assert_eq!(major_minor_version("v18.2.0"), Some(String::from("v18.2")));
+    }
+
+    #[test]
+    fn major_minor_version_is_none_without_a_version_number() {
+        assert_eq!(major_minor_version("command not found"), None);
+    }
+
+    #[test]
+    fn has_marker_file_matches_a_literal_filename() {
+        let dir = TempDir::new("languages").unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+
+        assert!(has_marker_file(dir.path(), &["Cargo.toml"]));
+        assert!(!has_marker_file(dir.path(), &["package.json"]));
+    }
+
+    #[test]
+    fn has_marker_file_matches_a_glob_extension() {
+        let dir = TempDir::new("languages").unwrap();
+        fs::write(dir.path().join("main.py"), "").unwrap();
+
+        assert!(has_marker_file(dir.path(), &["*.py"]));
+        assert!(!has_marker_file(dir.path(), &["*.rs"]));
+    }
+
+    #[test]
+    fn detect_skips_modules_whose_marker_file_is_absent() {
+        let dir = TempDir::new("languages").unwrap();
+        let found = detect(dir.path(), &[String::from("rust")]);
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn cached_version_output_is_used_within_the_ttl() {
+        let mut cache = VersionCache::default();
+        cache.entries.insert(
+            String::from("rust"),
+            CacheEntry {
+                version_output: String::from("rustc 1.72.0"),
+                cached_at: now_secs(),
+            },
+        );
+
+        let module = &LANGUAGE_MODULES[2];
+        assert_eq!(module.name, "rust");
+        assert_eq!(
+            cached_version_output(&cache, module),
+            Some(String::from("rustc 1.72.0"))
+        );
+    }
+
+    #[test]
+    fn cached_version_output_expires_after_the_ttl() {
+        let mut cache = VersionCache::default();
+        cache.entries.insert(
+            String::from("rust"),
+            CacheEntry {
+                version_output: String::from("rustc 1.72.0"),
+                cached_at: now_secs().saturating_sub(CACHE_TTL_SECS + 1),
+            },
+        );
+
+        let module = &LANGUAGE_MODULES[2];
+        assert_eq!(cached_version_output(&cache, module), None);
+    }
+}
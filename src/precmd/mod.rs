@@ -1,62 +1,459 @@
+use ansi_term::Colour::{self, Blue, Cyan, Fixed, Red, White, Yellow};
+use atty::Stream;
 use clap::ArgMatches;
 use dirs;
-use git2::{ErrorCode, Repository, Status, StatusOptions};
+use git2::{BranchType, ErrorCode, Oid, Repository, RepositoryState, Status, StatusOptions};
 use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
 use std::env;
 use std::fmt;
+use std::fs;
+use std::path::Path;
 use tico::tico;
 
+mod languages;
 #[cfg(test)]
 mod tests;
 
+/// The segments that make up the rendered prompt line, in the order they're written out.
+///
+/// `Dirty` and `AheadBehind` only render when there is a branch name (i.e. inside a git
+/// repository), since they describe the current branch rather than standing on their own.
+/// They do not check whether `Branch` itself is among the configured segments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Segment {
+    Path,
+    Languages,
+    Branch,
+    /// Renders a single `symbols.dirty` marker when the working tree has any pending change.
+    /// Per-category markers (staged/unstaged/untracked/conflicted) are intentionally opt-in via
+    /// `--status-counts` rather than the default — see `StatusCounts::render_indicators`.
+    Dirty,
+    AheadBehind,
+    CmdDuration,
+    UserHost,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+struct SymbolsConfig {
+    dirty: String,
+    ahead: String,
+    behind: String,
+    user_host_separator: String,
+}
+
+impl Default for SymbolsConfig {
+    fn default() -> Self {
+        SymbolsConfig {
+            dirty: String::from("*"),
+            ahead: String::from("⭫"),
+            behind: String::from("⭭"),
+            user_host_separator: String::from("@"),
+        }
+    }
+}
+
+/// A color, either one of the named ANSI colors or a 256-color palette index.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum ColorValue {
+    Named(String),
+    Indexed(u8),
+}
+
+impl ColorValue {
+    fn to_colour(&self) -> Colour {
+        match self {
+            ColorValue::Indexed(n) => Fixed(*n),
+            ColorValue::Named(name) => match name.to_lowercase().as_str() {
+                "black" => Colour::Black,
+                "red" => Red,
+                "green" => Colour::Green,
+                "yellow" => Yellow,
+                "blue" => Blue,
+                "purple" => Colour::Purple,
+                "cyan" => Cyan,
+                "white" => White,
+                _ => Fixed(242),
+            },
+        }
+    }
+}
+
+/// Per-segment colors and bold weight, like Starship's per-module `style` strings.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+struct StylesConfig {
+    path: ColorValue,
+    branch: ColorValue,
+    dirty: ColorValue,
+    arrows: ColorValue,
+    user: ColorValue,
+    host: ColorValue,
+    /// Segments rendered in bold in addition to their color.
+    bold: Vec<Segment>,
+}
+
+impl Default for StylesConfig {
+    fn default() -> Self {
+        StylesConfig {
+            path: ColorValue::Named(String::from("blue")),
+            branch: ColorValue::Indexed(242),
+            dirty: ColorValue::Named(String::from("red")),
+            arrows: ColorValue::Named(String::from("cyan")),
+            user: ColorValue::Named(String::from("white")),
+            host: ColorValue::Indexed(242),
+            bold: vec![Segment::Path, Segment::Branch],
+        }
+    }
+}
+
+/// Controls how the path segment shortens the current directory.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+struct PathConfig {
+    /// Inside a git repository, show the path relative to the repo root (with the repo's
+    /// directory name as the first visible component) instead of the full path from HOME.
+    truncate_to_repo_root: bool,
+    /// Caps the repo-relative path to this many trailing components. `None` shows all of it.
+    truncation_length: Option<usize>,
+}
+
+impl Default for PathConfig {
+    fn default() -> Self {
+        PathConfig {
+            truncate_to_repo_root: false,
+            truncation_length: None,
+        }
+    }
+}
+
+/// Controls which language/runtime modules (see [`languages`]) the `Languages` segment probes
+/// for, and in what order.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+struct LanguagesConfig {
+    /// Names of the built-in modules to run, in order. Empty means "run all of them".
+    enabled: Vec<String>,
+}
+
+impl Default for LanguagesConfig {
+    fn default() -> Self {
+        LanguagesConfig {
+            enabled: vec![
+                String::from("node"),
+                String::from("python"),
+                String::from("rust"),
+            ],
+        }
+    }
+}
+
+/// Controls when the command-duration segment renders.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+struct CmdDurationConfig {
+    /// Only show the previous command's duration once it took at least this long.
+    threshold_ms: u64,
+}
+
+impl Default for CmdDurationConfig {
+    fn default() -> Self {
+        CmdDurationConfig { threshold_ms: 2000 }
+    }
+}
+
+/// User-configurable prompt layout, read from `~/.config/pursue/pursue.toml`.
+///
+/// Any field (or whole section) the user omits falls back to [`Config::default`], so an
+/// empty or missing file keeps the original symbols, colors and separator, with the
+/// `Languages` and `CmdDuration` segments added to the original segment order.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+struct Config {
+    segments: Vec<Segment>,
+    symbols: SymbolsConfig,
+    styles: StylesConfig,
+    path: PathConfig,
+    languages: LanguagesConfig,
+    cmd_duration: CmdDurationConfig,
+    /// Written between each top-level segment (path, languages, branch, cmd duration,
+    /// user@host) and between the individual detected languages.
+    separator: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            segments: vec![
+                Segment::Path,
+                Segment::Languages,
+                Segment::Branch,
+                Segment::Dirty,
+                Segment::AheadBehind,
+                Segment::CmdDuration,
+                Segment::UserHost,
+            ],
+            symbols: SymbolsConfig::default(),
+            styles: StylesConfig::default(),
+            path: PathConfig::default(),
+            languages: LanguagesConfig::default(),
+            cmd_duration: CmdDurationConfig::default(),
+            separator: String::from(" "),
+        }
+    }
+}
+
+/// Reads `~/.config/pursue/pursue.toml`, falling back to [`Config::default`] when the file
+/// is missing or fails to parse.
+fn load_config() -> Config {
+    dirs::config_dir()
+        .map(|dir| dir.join("pursue").join("pursue.toml"))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
 #[derive(Debug)]
 struct PrePrompt {
     path: String,
     user_name: String,
     host: String,
     vcs_branch: String,
-    vcs_is_dirty: bool,
+    vcs_state: String,
+    vcs_status_counts: StatusCounts,
+    vcs_show_status_counts: bool,
     vcs_is_behind_remote: bool,
     vcs_is_ahead_of_remote: bool,
+    vcs_behind_count: u32,
+    vcs_ahead_count: u32,
+    cmd_duration: Option<u64>,
+    languages: Vec<String>,
+    config: Config,
+    no_color: bool,
+}
+
+impl PrePrompt {
+    /// Paints `text` in `color`, bolding it if `segment` is listed in `styles.bold`.
+    ///
+    /// Returns `text` unchanged when `--no-color` was passed or stdout isn't a TTY.
+    fn paint(&self, segment: Segment, color: &ColorValue, text: &str) -> String {
+        if self.no_color {
+            return String::from(text);
+        }
+
+        let style = color.to_colour().normal();
+        let style = if self.config.styles.bold.contains(&segment) {
+            style.bold()
+        } else {
+            style
+        };
+        style.paint(text).to_string()
+    }
 }
 
 impl fmt::Display for PrePrompt {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // Always write the path
-        write!(f, "{}", self.path)?;
+        let mut output = String::new();
 
-        // Write out the branch name if we are in a VCS directory.
-        if !self.vcs_branch.is_empty() {
-            write!(f, " {}", self.vcs_branch)?;
+        for segment in &self.config.segments {
+            match segment {
+                Segment::Path => {
+                    if !output.is_empty() {
+                        output.push_str(&self.config.separator);
+                    }
+                    output.push_str(&self.paint(*segment, &self.config.styles.path, &self.path));
+                }
+                Segment::Languages => {
+                    if self.languages.is_empty() {
+                        continue;
+                    }
+                    if !output.is_empty() {
+                        output.push_str(&self.config.separator);
+                    }
+                    output.push_str(&self.languages.join(&self.config.separator));
+                }
+                Segment::Branch => {
+                    if self.vcs_branch.is_empty() {
+                        continue;
+                    }
+                    if !output.is_empty() {
+                        output.push_str(&self.config.separator);
+                    }
+                    output.push_str(&self.paint(
+                        *segment,
+                        &self.config.styles.branch,
+                        &self.vcs_branch,
+                    ));
 
-            // Print a star if the working directory is dirty.
-            if self.vcs_is_dirty {
-                write!(f, "*")?;
-            }
+                    // Print the in-progress operation, if any, right after the branch name.
+                    if !self.vcs_state.is_empty() {
+                        output.push(' ');
+                        output.push_str(&self.vcs_state);
+                    }
+                }
+                Segment::Dirty => {
+                    if self.vcs_branch.is_empty() {
+                        continue;
+                    }
 
-            // Print arrows corresponding to whether or not we are out of date
-            // or ahead of the branch's remote.
-            if self.vcs_is_behind_remote {
-                write!(f, "⭭")?;
-            }
+                    // Print the per-category status counts if the user opted in, otherwise
+                    // fall back to a single marker when the working directory is dirty. This
+                    // fallback is intentional, not a gap: distinct at-a-glance markers are
+                    // available via --status-counts rather than on by default.
+                    if self.vcs_show_status_counts {
+                        let indicators = self.vcs_status_counts.render_indicators();
+                        if !indicators.is_empty() {
+                            output.push(' ');
+                            output.push_str(&self.paint(
+                                *segment,
+                                &self.config.styles.dirty,
+                                &indicators,
+                            ));
+                        }
+                    } else if self.vcs_status_counts.is_dirty() {
+                        output.push_str(&self.paint(
+                            *segment,
+                            &self.config.styles.dirty,
+                            &self.config.symbols.dirty,
+                        ));
+                    }
+                }
+                Segment::AheadBehind => {
+                    if self.vcs_branch.is_empty() {
+                        continue;
+                    }
+
+                    // Print arrows corresponding to whether or not we are out of date
+                    // or ahead of the branch's remote, along with how many commits by.
+                    if self.vcs_is_behind_remote {
+                        let mut behind = self.config.symbols.behind.clone();
+                        if self.vcs_behind_count > 0 {
+                            behind.push_str(&self.vcs_behind_count.to_string());
+                        }
+                        output.push_str(&self.paint(*segment, &self.config.styles.arrows, &behind));
+                    }
 
-            if self.vcs_is_ahead_of_remote {
-                write!(f, "⭫")?;
+                    if self.vcs_is_ahead_of_remote {
+                        let mut ahead = self.config.symbols.ahead.clone();
+                        if self.vcs_ahead_count > 0 {
+                            ahead.push_str(&self.vcs_ahead_count.to_string());
+                        }
+                        output.push_str(&self.paint(*segment, &self.config.styles.arrows, &ahead));
+                    }
+                }
+                Segment::CmdDuration => {
+                    let duration_ms = match self.cmd_duration {
+                        Some(ms) if ms >= self.config.cmd_duration.threshold_ms => ms,
+                        _ => continue,
+                    };
+                    if !output.is_empty() {
+                        output.push_str(&self.config.separator);
+                    }
+                    output.push_str(&format_duration(duration_ms));
+                }
+                Segment::UserHost => {
+                    if self.user_name.is_empty() || self.host.is_empty() {
+                        continue;
+                    }
+                    if !output.is_empty() {
+                        output.push_str(&self.config.separator);
+                    }
+                    output.push_str(&self.paint(*segment, &self.config.styles.user, &self.user_name));
+                    output.push_str(&self.config.symbols.user_host_separator);
+                    output.push_str(&self.paint(*segment, &self.config.styles.host, &self.host));
+                }
             }
         }
 
-        // Write out the host name in the form user@host if both are set.
-        if !self.user_name.is_empty() && !self.host.is_empty() {
-            write!(f, " {}@{}", self.user_name, self.host)?;
+        write!(f, "{}", output)
+    }
+}
+
+/// Machine-readable form of a [`StatusCounts`], used by the `--format json` output mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct JsonStatusCounts {
+    staged: u32,
+    modified: u32,
+    untracked: u32,
+    deleted: u32,
+    renamed: u32,
+    conflicted: u32,
+    stashed: u32,
+}
+
+impl From<StatusCounts> for JsonStatusCounts {
+    fn from(counts: StatusCounts) -> Self {
+        JsonStatusCounts {
+            staged: counts.staged,
+            modified: counts.modified,
+            untracked: counts.untracked,
+            deleted: counts.deleted,
+            renamed: counts.renamed,
+            conflicted: counts.conflicted,
+            stashed: counts.stashed,
         }
+    }
+}
+
+/// Machine-readable form of a [`PrePrompt`], used by the `--format json` output mode so other
+/// prompt frameworks and status bars can consume pursue's git detection without parsing
+/// ANSI-painted text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct JsonPrePrompt {
+    path: String,
+    user_name: String,
+    host: String,
+    branch: String,
+    state: String,
+    status: JsonStatusCounts,
+    is_behind_remote: bool,
+    is_ahead_of_remote: bool,
+    behind_count: u32,
+    ahead_count: u32,
+    cmd_duration_ms: Option<u64>,
+    languages: Vec<String>,
+}
 
-        Ok(())
+impl From<&PrePrompt> for JsonPrePrompt {
+    fn from(precmd: &PrePrompt) -> Self {
+        JsonPrePrompt {
+            path: precmd.path.clone(),
+            user_name: precmd.user_name.clone(),
+            host: precmd.host.clone(),
+            branch: precmd.vcs_branch.clone(),
+            state: precmd.vcs_state.clone(),
+            status: precmd.vcs_status_counts.into(),
+            is_behind_remote: precmd.vcs_is_behind_remote,
+            is_ahead_of_remote: precmd.vcs_is_ahead_of_remote,
+            behind_count: precmd.vcs_behind_count,
+            ahead_count: precmd.vcs_ahead_count,
+            cmd_duration_ms: precmd.cmd_duration,
+            languages: precmd.languages.clone(),
+        }
     }
 }
 
 /// Formats the current path to replace the path of HOME with the usual '~' as
 /// well as shorten the directory names if requested.
-fn format_path(cwd: &str, home_dir: &str, shorten: bool) -> String {
+///
+/// When `repo_root` is given and `path_config.truncate_to_repo_root` is enabled, the path
+/// is rendered relative to the repository root instead, per [`repo_relative_path`].
+fn format_path(
+    cwd: &str,
+    home_dir: &str,
+    shorten: bool,
+    repo_root: Option<&str>,
+    path_config: &PathConfig,
+) -> String {
+    if path_config.truncate_to_repo_root {
+        if let Some(repo_root) = repo_root {
+            return repo_relative_path(cwd, repo_root, path_config.truncation_length);
+        }
+    }
+
     let path = Regex::new(home_dir).unwrap().replace(cwd, "~");
 
     if shorten {
@@ -66,36 +463,317 @@ fn format_path(cwd: &str, home_dir: &str, shorten: bool) -> String {
     String::from(path)
 }
 
-fn branch_name(repo: &Repository) -> String {
+/// Renders `cwd` relative to `repo_root`, with the repo's directory name standing in for
+/// HOME as the first visible component, e.g. `pursue/src/precmd` for a repo named `pursue`.
+///
+/// Capped to `truncation_length` trailing components when given, the way Starship's
+/// directory module truncates long paths.
+fn repo_relative_path(cwd: &str, repo_root: &str, truncation_length: Option<usize>) -> String {
+    let repo_root = repo_root.trim_end_matches('/');
+    let repo_name = Path::new(repo_root)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(repo_root);
+
+    let relative = cwd.strip_prefix(repo_root).unwrap_or(cwd).trim_start_matches('/');
+
+    let mut components: Vec<&str> = vec![repo_name];
+    if !relative.is_empty() {
+        components.extend(relative.split('/'));
+    }
+
+    if let Some(length) = truncation_length {
+        if components.len() > length {
+            let skip = components.len() - length;
+            components.drain(0..skip);
+        }
+    }
+
+    components.join("/")
+}
+
+/// Formats milliseconds as a human-readable duration, e.g. `1.2s` or `2m34s`, the way
+/// Starship's cmd_duration module does.
+fn format_duration(ms: u64) -> String {
+    let total_seconds = ms / 1000;
+    let minutes = total_seconds / 60;
+    let seconds = total_seconds % 60;
+
+    if minutes > 0 {
+        format!("{}m{}s", minutes, seconds)
+    } else {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    }
+}
+
+/// The ref HEAD currently points at.
+#[derive(Debug, Clone, PartialEq)]
+crate enum Branch {
+    /// HEAD points at a local branch with the given name.
+    Named(String),
+    /// HEAD points directly at a commit (a rebase, bisect, or checked-out tag/commit).
+    Detached(Oid),
+    /// The repository has no commits yet.
+    Unborn,
+}
+
+impl Branch {
+    /// The branch name as rendered in the prompt: the branch name, a short commit hash when
+    /// detached, or "master" for a brand new repo with no commits yet.
+    crate fn display_name(&self) -> String {
+        match self {
+            Branch::Named(name) => name.clone(),
+            Branch::Detached(oid) => oid.to_string().chars().take(7).collect(),
+            Branch::Unborn => String::from("master"),
+        }
+    }
+}
+
+/// Finds what HEAD currently points at.
+///
+/// If the repository was just initialized and doesn't have any commits, `Branch::Unborn` is
+/// returned. If HEAD is detached (a rebase, bisect, or checked-out tag/commit), `Branch::Detached`
+/// carries HEAD's commit OID so the caller can fall back to showing a short hash.
+crate fn branch_name(repo: &Repository) -> Branch {
     match repo.head() {
-        Ok(head) => head.shorthand().unwrap().to_string(),
+        Ok(head) => {
+            if head.is_branch() {
+                Branch::Named(head.shorthand().unwrap().to_string())
+            } else {
+                match head.target() {
+                    Some(oid) => Branch::Detached(oid),
+                    None => Branch::Named(String::from("")),
+                }
+            }
+        }
         Err(e) => {
             // In a new repo with no commits, HEAD points to a branch with no
             // commits. So let's just call the branch 'master'.
             if e.code() == ErrorCode::UnbornBranch {
-                String::from("master")
+                Branch::Unborn
             } else {
-                String::from("")
+                Branch::Named(String::from(""))
             }
         }
     }
 }
 
-fn is_dirty(repo: &Repository) -> bool {
+/// Per-category counts of a repository's pending changes, the way Starship's git_status
+/// module breaks things down.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+crate struct StatusCounts {
+    staged: u32,
+    modified: u32,
+    untracked: u32,
+    deleted: u32,
+    renamed: u32,
+    conflicted: u32,
+    stashed: u32,
+    /// Whether any working-tree-side change is pending (`WT_*` flags only). `deleted`/
+    /// `renamed` above fold in their index-side counterparts too, so they can't be used
+    /// directly to tell staged-only deletes/renames apart from working-tree ones.
+    working_tree_dirty: bool,
+}
+
+impl StatusCounts {
+    fn is_dirty(&self) -> bool {
+        self.staged > 0
+            || self.modified > 0
+            || self.untracked > 0
+            || self.deleted > 0
+            || self.renamed > 0
+            || self.conflicted > 0
+    }
+
+    crate fn is_staged(&self) -> bool {
+        self.staged > 0
+    }
+
+    crate fn is_unstaged(&self) -> bool {
+        self.working_tree_dirty
+    }
+
+    crate fn is_untracked(&self) -> bool {
+        self.untracked > 0
+    }
+
+    /// Renders the compact `+2 ~3 -1 ?4 ⚠1 ⚑1` indicator string, omitting any category
+    /// whose count is zero.
+    fn render_indicators(&self) -> String {
+        let mut parts = Vec::new();
+        if self.staged > 0 {
+            parts.push(format!("+{}", self.staged));
+        }
+        if self.modified > 0 {
+            parts.push(format!("~{}", self.modified));
+        }
+        if self.deleted > 0 {
+            parts.push(format!("-{}", self.deleted));
+        }
+        if self.renamed > 0 {
+            parts.push(format!("»{}", self.renamed));
+        }
+        if self.untracked > 0 {
+            parts.push(format!("?{}", self.untracked));
+        }
+        if self.conflicted > 0 {
+            parts.push(format!("⚠{}", self.conflicted));
+        }
+        if self.stashed > 0 {
+            parts.push(format!("⚑{}", self.stashed));
+        }
+        parts.join(" ")
+    }
+}
+
+crate fn status_counts(repo: &mut Repository) -> StatusCounts {
     let mut options = StatusOptions::new();
     options.include_untracked(true);
 
     let statuses = match repo.statuses(Some(&mut options)) {
         Ok(statuses) => statuses,
-        Err(_) => return false,
+        Err(_) => return StatusCounts::default(),
+    };
+
+    // Index-side deletes/renames count as both staged (there's a change sitting in the
+    // index, e.g. from `git rm`/`git mv`) and deleted/renamed, so a staged deletion shows
+    // up as both `+1` and `-1` rather than disappearing from the staged tally.
+    let staged_mask = Status::INDEX_NEW
+        | Status::INDEX_MODIFIED
+        | Status::INDEX_TYPECHANGE
+        | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED;
+    let modified_mask = Status::WT_MODIFIED | Status::WT_TYPECHANGE;
+    let deleted_mask = Status::INDEX_DELETED | Status::WT_DELETED;
+    let renamed_mask = Status::INDEX_RENAMED | Status::WT_RENAMED;
+
+    let mut counts = StatusCounts::default();
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.intersects(staged_mask) {
+            counts.staged += 1;
+        }
+        if status.intersects(modified_mask) {
+            counts.modified += 1;
+        }
+        if status.intersects(deleted_mask) {
+            counts.deleted += 1;
+        }
+        if status.intersects(renamed_mask) {
+            counts.renamed += 1;
+        }
+        if status.intersects(modified_mask | Status::WT_DELETED | Status::WT_RENAMED) {
+            counts.working_tree_dirty = true;
+        }
+        if status.intersects(Status::WT_NEW) {
+            counts.untracked += 1;
+        }
+        if status.intersects(Status::CONFLICTED) {
+            counts.conflicted += 1;
+        }
+    }
+
+    counts.stashed = stash_count(repo);
+    counts
+}
+
+/// Counts the number of stashes.
+fn stash_count(repo: &mut Repository) -> u32 {
+    let mut count = 0;
+    let _ = repo.stash_foreach(|_, _, _| {
+        count += 1;
+        true
+    });
+    count
+}
+
+/// Determines how many commits the current branch is ahead/behind its upstream.
+///
+/// Returns `(is_behind, is_ahead, behind_count, ahead_count)`. If HEAD isn't a branch, or the
+/// branch has no upstream configured, everything is reported as up to date.
+crate fn ahead_behind_remote(repo: &Repository) -> (bool, bool, u32, u32) {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(_) => return (false, false, 0, 0),
     };
 
-    let mut clean_status = Status::empty();
-    clean_status.toggle(Status::CURRENT);
-    clean_status.toggle(Status::IGNORED);
-    statuses
-        .iter()
-        .any(|entry| !entry.status().is_empty() && !entry.status().intersects(clean_status))
+    let local_oid = match head.target() {
+        Some(oid) => oid,
+        None => return (false, false, 0, 0),
+    };
+
+    let branch_name = match head.shorthand() {
+        Some(name) => name,
+        None => return (false, false, 0, 0),
+    };
+
+    let upstream_oid = match repo
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|branch| branch.upstream())
+        .ok()
+        .and_then(|upstream| upstream.get().target())
+    {
+        Some(oid) => oid,
+        None => return (false, false, 0, 0),
+    };
+
+    match repo.graph_ahead_behind(local_oid, upstream_oid) {
+        Ok((ahead, behind)) => (behind > 0, ahead > 0, behind as u32, ahead as u32),
+        Err(_) => (false, false, 0, 0),
+    }
+}
+
+/// Describes a repository's in-progress operation, e.g. `REBASING 2/5`.
+///
+/// Returns an empty string if the repository isn't in the middle of a merge, rebase,
+/// cherry-pick, revert, or bisect.
+fn state_label(repo: &Repository) -> String {
+    match repo.state() {
+        RepositoryState::Merge => String::from("MERGING"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => String::from("REVERTING"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+            String::from("CHERRY-PICKING")
+        }
+        RepositoryState::Bisect => String::from("BISECTING"),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => match rebase_progress(repo) {
+            Some((msgnum, end)) => format!("REBASING {}/{}", msgnum, end),
+            None => String::from("REBASING"),
+        },
+        _ => String::from(""),
+    }
+}
+
+/// Detects the username and hostname of the current SSH session, feeding the `UserHost`
+/// segment.
+///
+/// Returns `None` outside of an SSH session, so the segment only renders when it's actually
+/// informative (e.g. not on a local terminal).
+fn ssh_user_host() -> Option<(String, String)> {
+    env::var("SSH_CONNECTION").ok()?;
+    let user = env::var("USER").ok()?;
+    // $HOSTNAME isn't a posix defined environment variable and sometimes doesn't exist when
+    // called from a new `sh` process instead of `bash` or `zsh` where it is often predefined.
+    // In order to still get the hostname, we'll just parse it directly from the hostname file.
+    let host = fs::read_to_string("/etc/hostname").ok()?.trim().to_string();
+    Some((user, host))
+}
+
+/// Reads the current step and total step count out of `.git/rebase-merge`.
+fn rebase_progress(repo: &Repository) -> Option<(u32, u32)> {
+    let rebase_merge = repo.path().join("rebase-merge");
+    let msgnum = fs::read_to_string(rebase_merge.join("msgnum"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    let end = fs::read_to_string(rebase_merge.join("end"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some((msgnum, end))
 }
 
 /// Prints out the pre-command line of the prompt.
@@ -110,9 +788,19 @@ crate fn render(sub_matchings: &ArgMatches) {
         user_name: String::from(""),
         host: String::from(""),
         vcs_branch: String::from(""),
-        vcs_is_dirty: false,
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: sub_matchings.is_present("status-counts"),
         vcs_is_behind_remote: false,
         vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: sub_matchings
+            .value_of("cmd-duration")
+            .and_then(|ms| ms.parse().ok()),
+        languages: Vec::new(),
+        config: load_config(),
+        no_color: sub_matchings.is_present("no-color") || !atty::is(Stream::Stdout),
     };
 
     let shorten = sub_matchings.is_present("shorten");
@@ -121,12 +809,45 @@ crate fn render(sub_matchings: &ArgMatches) {
         Some(dir) => String::from(dir.to_str().unwrap()),
         _ => String::from(""),
     };
-    precmd.path = format_path(working_dir.to_str().unwrap(), &home_dir, shorten);
 
-    if let Some(repo) = Repository::discover(".").ok() {
-        precmd.vcs_branch = branch_name(&repo);
-        precmd.vcs_is_dirty = is_dirty(&repo);
+    let repo = Repository::discover(".").ok();
+    let repo_root = repo
+        .as_ref()
+        .and_then(|repo| repo.workdir())
+        .and_then(|path| path.to_str())
+        .map(String::from);
+
+    precmd.path = format_path(
+        working_dir.to_str().unwrap(),
+        &home_dir,
+        shorten,
+        repo_root.as_deref(),
+        &precmd.config.path,
+    );
+    if precmd.config.segments.contains(&Segment::Languages) {
+        precmd.languages = languages::detect(&working_dir, &precmd.config.languages.enabled);
+    }
+    if let Some((user, host)) = ssh_user_host() {
+        precmd.user_name = user;
+        precmd.host = host;
     }
 
-    println!("{}", precmd);
+    if let Some(mut repo) = repo {
+        precmd.vcs_branch = branch_name(&repo).display_name();
+        precmd.vcs_state = state_label(&repo);
+        precmd.vcs_status_counts = status_counts(&mut repo);
+
+        let (is_behind, is_ahead, behind_count, ahead_count) = ahead_behind_remote(&repo);
+        precmd.vcs_is_behind_remote = is_behind;
+        precmd.vcs_is_ahead_of_remote = is_ahead;
+        precmd.vcs_behind_count = behind_count;
+        precmd.vcs_ahead_count = ahead_count;
+    }
+
+    if sub_matchings.value_of("format") == Some("json") {
+        let json = JsonPrePrompt::from(&precmd);
+        println!("{}", serde_json::to_string(&json).unwrap());
+    } else {
+        println!("{}", precmd);
+    }
 }
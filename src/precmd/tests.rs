@@ -1,6 +1,8 @@
 #[cfg(test)]
 use super::*;
+use ansi_term::Colour;
 use git2::ObjectType;
+use std::fs;
 use std::fs::File;
 use std::path::Path;
 use tempdir::TempDir;
@@ -12,9 +14,17 @@ fn pre_prompt_only_path_prints_just_the_path() {
         user_name: String::from(""),
         host: String::from(""),
         vcs_branch: String::from(""),
-        vcs_is_dirty: false,
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
         vcs_is_behind_remote: false,
         vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
     };
 
     assert_eq!("~/some/dir".to_owned(), format!("{}", precmd));
@@ -27,9 +37,17 @@ fn pre_prompt_prints_just_path_when_only_has_username() {
         user_name: String::from("user_name"),
         host: String::from(""),
         vcs_branch: String::from(""),
-        vcs_is_dirty: false,
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
         vcs_is_behind_remote: false,
         vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
     };
 
     assert_eq!("~/some/dir".to_owned(), format!("{}", precmd));
@@ -42,9 +60,17 @@ fn pre_prompt_prints_user_name_and_host() {
         user_name: String::from("user"),
         host: String::from("host"),
         vcs_branch: String::from(""),
-        vcs_is_dirty: false,
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
         vcs_is_behind_remote: false,
         vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
     };
 
     assert_eq!("~ user@host".to_owned(), format!("{}", precmd));
@@ -57,9 +83,17 @@ fn pre_prompt_prints_branch_name() {
         user_name: String::from("user"),
         host: String::from("host"),
         vcs_branch: String::from("master"),
-        vcs_is_dirty: false,
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
         vcs_is_behind_remote: false,
         vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
     };
 
     assert_eq!("~ master user@host".to_owned(), format!("{}", precmd));
@@ -72,9 +106,20 @@ fn pre_prompt_prints_dirty() {
         user_name: String::from("user"),
         host: String::from("host"),
         vcs_branch: String::from("master"),
-        vcs_is_dirty: true,
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts {
+            staged: 1,
+            ..StatusCounts::default()
+        },
+        vcs_show_status_counts: false,
         vcs_is_behind_remote: false,
         vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
     };
 
     assert_eq!("~ master* user@host".to_owned(), format!("{}", precmd));
@@ -87,37 +132,483 @@ fn pre_prompt_prints_dirty_upstream_downstream() {
         user_name: String::from("user"),
         host: String::from("host"),
         vcs_branch: String::from("master"),
-        vcs_is_dirty: true,
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts {
+            staged: 1,
+            ..StatusCounts::default()
+        },
+        vcs_show_status_counts: false,
         vcs_is_behind_remote: true,
         vcs_is_ahead_of_remote: true,
+        vcs_behind_count: 3,
+        vcs_ahead_count: 2,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
     };
 
     assert_eq!(
-        "~ master*⭭⭫ user@host".to_owned(),
+        "~ master*⭭3⭫2 user@host".to_owned(),
         format!("{}", precmd)
     );
 }
 
+#[test]
+fn pre_prompt_prints_bare_arrows_when_counts_are_zero() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from("user"),
+        host: String::from("host"),
+        vcs_branch: String::from("master"),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: true,
+        vcs_is_ahead_of_remote: true,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
+    };
+
+    assert_eq!("~ master⭭⭫ user@host".to_owned(), format!("{}", precmd));
+}
+
+#[test]
+fn pre_prompt_prints_state_after_branch_name() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from("user"),
+        host: String::from("host"),
+        vcs_branch: String::from("master"),
+        vcs_state: String::from("REBASING 2/5"),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
+    };
+
+    assert_eq!(
+        "~ master REBASING 2/5 user@host".to_owned(),
+        format!("{}", precmd)
+    );
+}
+
+#[test]
+fn pre_prompt_disables_segment_via_config() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from("user"),
+        host: String::from("host"),
+        vcs_branch: String::from("master"),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts {
+            staged: 1,
+            ..StatusCounts::default()
+        },
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config {
+            segments: vec![Segment::Path, Segment::Branch, Segment::UserHost],
+            ..Config::default()
+        },
+        no_color: true,
+    };
+
+    assert_eq!("~ master user@host".to_owned(), format!("{}", precmd));
+}
+
+#[test]
+fn pre_prompt_honors_custom_symbols_and_separator() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from("user"),
+        host: String::from("host"),
+        vcs_branch: String::from("master"),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts {
+            staged: 1,
+            ..StatusCounts::default()
+        },
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config {
+            separator: String::from(" | "),
+            symbols: SymbolsConfig {
+                dirty: String::from("[dirty]"),
+                user_host_separator: String::from(" at "),
+                ..SymbolsConfig::default()
+            },
+            ..Config::default()
+        },
+        no_color: true,
+    };
+
+    assert_eq!(
+        "~ | master[dirty] | user at host".to_owned(),
+        format!("{}", precmd)
+    );
+}
+
+#[test]
+fn languages_segment_is_hidden_when_nothing_was_detected() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from(""),
+        host: String::from(""),
+        vcs_branch: String::from(""),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
+    };
+
+    assert_eq!("~".to_owned(), format!("{}", precmd));
+}
+
+#[test]
+fn languages_segment_prints_each_detected_module() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from(""),
+        host: String::from(""),
+        vcs_branch: String::from(""),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: vec![String::from("⬢ v18.2"), String::from("🦀 1.72")],
+        config: Config::default(),
+        no_color: true,
+    };
+
+    assert_eq!("~ ⬢ v18.2 🦀 1.72".to_owned(), format!("{}", precmd));
+}
+
+#[test]
+fn cmd_duration_is_hidden_below_the_threshold() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from(""),
+        host: String::from(""),
+        vcs_branch: String::from(""),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: Some(1999),
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
+    };
+
+    assert_eq!("~".to_owned(), format!("{}", precmd));
+}
+
+#[test]
+fn cmd_duration_is_shown_above_the_threshold() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from(""),
+        host: String::from(""),
+        vcs_branch: String::from(""),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: Some(2500),
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
+    };
+
+    assert_eq!("~ 2.5s".to_owned(), format!("{}", precmd));
+}
+
+#[test]
+fn cmd_duration_honors_a_custom_threshold() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from(""),
+        host: String::from(""),
+        vcs_branch: String::from(""),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: Some(500),
+        languages: Vec::new(),
+        config: Config {
+            cmd_duration: CmdDurationConfig { threshold_ms: 100 },
+            ..Config::default()
+        },
+        no_color: true,
+    };
+
+    assert_eq!("~ 0.5s".to_owned(), format!("{}", precmd));
+}
+
+#[test]
+fn format_duration_formats_sub_minute_durations_with_decimal_seconds() {
+    assert_eq!(format_duration(1200), "1.2s");
+    assert_eq!(format_duration(500), "0.5s");
+}
+
+#[test]
+fn format_duration_formats_minutes_and_whole_seconds() {
+    assert_eq!(format_duration(154_000), "2m34s");
+}
+
+#[test]
+fn no_color_suppresses_ansi_escape_codes() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from("user"),
+        host: String::from("host"),
+        vcs_branch: String::from("master"),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: true,
+    };
+
+    let rendered = format!("{}", precmd);
+    assert_eq!(rendered, "~ master user@host");
+    assert!(!rendered.contains('\x1b'));
+}
+
+#[test]
+fn colored_output_paints_the_path_segment() {
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from(""),
+        host: String::from(""),
+        vcs_branch: String::from(""),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: false,
+    };
+
+    let rendered = format!("{}", precmd);
+    assert!(rendered.contains('\x1b'), "expected ANSI escapes in {:?}", rendered);
+    assert!(rendered.contains("~"));
+}
+
+#[test]
+fn bold_is_only_applied_to_configured_segments() {
+    let path_style = Colour::Blue.normal();
+    let bold_style = Colour::Blue.bold();
+
+    assert_ne!(path_style.paint("~").to_string(), bold_style.paint("~").to_string());
+
+    let precmd = PrePrompt {
+        path: String::from("~"),
+        user_name: String::from(""),
+        host: String::from(""),
+        vcs_branch: String::from(""),
+        vcs_state: String::from(""),
+        vcs_status_counts: StatusCounts::default(),
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: false,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 0,
+        vcs_ahead_count: 0,
+        cmd_duration: None,
+        languages: Vec::new(),
+        config: Config::default(),
+        no_color: false,
+    };
+    let blue = ColorValue::Named(String::from("blue"));
+
+    // `Path` is in the default `styles.bold` list, `Dirty` isn't.
+    assert_eq!(precmd.paint(Segment::Path, &blue, "~"), bold_style.paint("~").to_string());
+    assert_eq!(precmd.paint(Segment::Dirty, &blue, "~"), path_style.paint("~").to_string());
+}
+
+#[test]
+fn config_defaults_keep_the_original_symbols_and_add_new_segments() {
+    let config = Config::default();
+    assert_eq!(
+        config.segments,
+        vec![
+            Segment::Path,
+            Segment::Languages,
+            Segment::Branch,
+            Segment::Dirty,
+            Segment::AheadBehind,
+            Segment::CmdDuration,
+            Segment::UserHost,
+        ]
+    );
+    assert_eq!(config.symbols.dirty, "*");
+    assert_eq!(config.separator, " ");
+}
+
+#[test]
+fn config_parses_partial_toml_and_fills_in_defaults() {
+    let config: Config = toml::from_str(
+        r#"
+        segments = ["userhost", "path"]
+
+        [symbols]
+        dirty = "!"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.segments, vec![Segment::UserHost, Segment::Path]);
+    assert_eq!(config.symbols.dirty, "!");
+    assert_eq!(config.symbols.ahead, SymbolsConfig::default().ahead);
+}
+
+#[test]
+fn styles_config_parses_partial_toml_and_fills_in_defaults() {
+    let config: Config = toml::from_str(
+        r#"
+        [styles]
+        path = 33
+        bold = ["branch"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.styles.path, ColorValue::Indexed(33));
+    assert_eq!(config.styles.bold, vec![Segment::Branch]);
+    assert_eq!(config.styles.arrows, StylesConfig::default().arrows);
+}
+
 #[test]
 fn format_path_home_is_shortened() {
-    let home = format_path("/home/user", "/home/user", false);
+    let home = format_path("/home/user", "/home/user", false, None, &PathConfig::default());
     assert_eq!(home, "~", "Home path {} wasn't shortened to '~'!", home);
 
-    let path = format_path("home/user/pursue/src", "home/user", false);
+    let path = format_path(
+        "home/user/pursue/src",
+        "home/user",
+        false,
+        None,
+        &PathConfig::default(),
+    );
     assert_eq!(path, "~/pursue/src");
 }
 
 #[test]
 fn format_path_non_current_directories_are_shortened() {
-    let long = format_path("home/user/Really/long/path", "home/user", true);
+    let long = format_path(
+        "home/user/Really/long/path",
+        "home/user",
+        true,
+        None,
+        &PathConfig::default(),
+    );
     assert_eq!(long, "~/R/l/path");
 }
 
+#[test]
+fn format_path_truncates_to_repo_root_when_enabled() {
+    let config = PathConfig {
+        truncate_to_repo_root: true,
+        truncation_length: None,
+    };
+
+    let path = format_path(
+        "/home/user/code/pursue/src/precmd",
+        "/home/user",
+        false,
+        Some("/home/user/code/pursue"),
+        &config,
+    );
+    assert_eq!(path, "pursue/src/precmd");
+}
+
+#[test]
+fn format_path_ignores_repo_root_when_truncation_is_disabled() {
+    let path = format_path(
+        "/home/user/code/pursue/src",
+        "/home/user",
+        false,
+        Some("/home/user/code/pursue"),
+        &PathConfig::default(),
+    );
+    assert_eq!(path, "~/code/pursue/src");
+}
+
+#[test]
+fn repo_relative_path_caps_to_truncation_length() {
+    let path = repo_relative_path(
+        "/home/user/code/pursue/src/precmd/mod",
+        "/home/user/code/pursue",
+        Some(2),
+    );
+    assert_eq!(path, "precmd/mod");
+}
+
+#[test]
+fn repo_relative_path_at_the_repo_root_is_just_the_repo_name() {
+    let path = repo_relative_path("/home/user/code/pursue", "/home/user/code/pursue", None);
+    assert_eq!(path, "pursue");
+}
+
 #[test]
 fn branch_name_uses_master_with_brand_new_repo() {
     let (_td, repo) = temp_repo();
     let branch = branch_name(&repo);
-    assert_eq!(branch, "master");
+    assert_eq!(branch, Branch::Unborn);
+    assert_eq!(branch.display_name(), "master");
 }
 
 #[test]
@@ -135,23 +626,42 @@ fn branch_name_returns_correct_name() {
     assert!(branch.is_head());
 
     let branch = branch_name(&repo);
-    assert_eq!(branch, "test_branch");
+    assert_eq!(branch, Branch::Named(String::from("test_branch")));
 }
 
 #[test]
-fn is_dirty_with_untracked_change() {
+fn branch_name_is_detached_on_detached_head() {
     let (_td, repo) = temp_repo();
     init_repo(&repo);
+    let head_oid = repo.head().unwrap().target().unwrap();
+
+    repo.set_head_detached(head_oid).unwrap();
+
+    assert_eq!(branch_name(&repo), Branch::Detached(head_oid));
+}
+
+#[test]
+fn branch_display_name_uses_a_short_oid_for_detached_head() {
+    let oid = Oid::from_str("abcdef0123456789abcdef0123456789abcdef01").unwrap();
+    assert_eq!(Branch::Detached(oid).display_name(), "abcdef0");
+}
+
+#[test]
+fn status_counts_with_untracked_change() {
+    let (_td, mut repo) = temp_repo();
+    init_repo(&repo);
 
     let root = repo.path().parent().unwrap();
     File::create(&root.join("unstaged_file")).unwrap();
 
-    assert!(is_dirty(&repo));
+    let counts = status_counts(&mut repo);
+    assert!(counts.is_dirty());
+    assert_eq!(counts.untracked, 1);
 }
 
 #[test]
-fn is_dirty_with_unstaged_change() {
-    let (_td, repo) = temp_repo();
+fn status_counts_with_staged_change() {
+    let (_td, mut repo) = temp_repo();
     init_repo(&repo);
     let mut index = repo.index().unwrap();
 
@@ -160,7 +670,183 @@ fn is_dirty_with_unstaged_change() {
     index.add_path(Path::new("unstaged_file")).unwrap();
     index.write().unwrap();
 
-    assert!(is_dirty(&repo));
+    let counts = status_counts(&mut repo);
+    assert!(counts.is_dirty());
+    assert_eq!(counts.staged, 1);
+}
+
+#[test]
+fn status_counts_with_staged_deletion() {
+    let (_td, mut repo) = temp_repo();
+    init_repo(&repo);
+    make_commit(&repo, "tracked_file", "add tracked_file");
+
+    let root = repo.path().parent().unwrap();
+    fs::remove_file(&root.join("tracked_file")).unwrap();
+    let mut index = repo.index().unwrap();
+    index.remove_path(Path::new("tracked_file")).unwrap();
+    index.write().unwrap();
+
+    let counts = status_counts(&mut repo);
+    assert_eq!(counts.staged, 1, "a staged deletion should still count as staged");
+    assert_eq!(counts.deleted, 1);
+    assert!(
+        !counts.is_unstaged(),
+        "a staged deletion with nothing else pending in the working tree isn't unstaged"
+    );
+}
+
+#[test]
+fn status_counts_counts_stashes() {
+    let (_td, mut repo) = temp_repo();
+    init_repo(&repo);
+    let mut index = repo.index().unwrap();
+
+    let root = repo.path().parent().unwrap();
+    File::create(&root.join("stashed_file")).unwrap();
+    index.add_path(Path::new("stashed_file")).unwrap();
+    index.write().unwrap();
+
+    let sig = repo.signature().unwrap();
+    repo.stash_save(&sig, "test stash", None).unwrap();
+
+    let counts = status_counts(&mut repo);
+    assert_eq!(counts.stashed, 1);
+}
+
+#[test]
+fn render_indicators_formats_each_nonzero_category() {
+    let counts = StatusCounts {
+        staged: 2,
+        modified: 3,
+        untracked: 4,
+        deleted: 1,
+        renamed: 0,
+        conflicted: 1,
+        stashed: 1,
+        working_tree_dirty: true,
+    };
+
+    assert_eq!(counts.render_indicators(), "+2 ~3 -1 ?4 ⚠1 ⚑1");
+}
+
+#[test]
+fn state_label_is_empty_for_clean_repo() {
+    let (_td, repo) = temp_repo();
+    init_repo(&repo);
+    assert_eq!(state_label(&repo), "");
+}
+
+#[test]
+fn state_label_reports_rebase_progress() {
+    let (_td, repo) = temp_repo();
+    init_repo(&repo);
+
+    let rebase_merge = repo.path().join("rebase-merge");
+    fs::create_dir(&rebase_merge).unwrap();
+    fs::write(rebase_merge.join("msgnum"), "2\n").unwrap();
+    fs::write(rebase_merge.join("end"), "5\n").unwrap();
+
+    assert_eq!(rebase_progress(&repo), Some((2, 5)));
+    assert_eq!(state_label(&repo), "REBASING 2/5");
+}
+
+#[test]
+fn unset_remote_is_not_ahead_or_behind() {
+    let (_td, repo) = temp_repo();
+    init_repo(&repo);
+    assert_eq!(ahead_behind_remote(&repo), (false, false, 0, 0));
+}
+
+#[test]
+fn ahead_behind_remote_reports_counts_once_upstream_is_tracked() {
+    let (_td, repo) = temp_repo();
+    init_repo(&repo);
+
+    let mut local = repo
+        .branch(
+            "local_branch",
+            &repo.head().unwrap().peel_to_commit().unwrap(),
+            false,
+        )
+        .unwrap();
+
+    repo.branch(
+        "remote_branch",
+        &repo.head().unwrap().peel_to_commit().unwrap(),
+        false,
+    )
+    .unwrap();
+    repo.set_head("refs/heads/remote_branch").unwrap();
+    repo.checkout_head(None).unwrap();
+    make_commit(&repo, "remote_file", "remote commit");
+
+    repo.set_head("refs/heads/local_branch").unwrap();
+    repo.checkout_head(None).unwrap();
+    assert_eq!(ahead_behind_remote(&repo), (false, false, 0, 0));
+
+    local.set_upstream(Some("remote_branch")).unwrap();
+    assert_eq!(ahead_behind_remote(&repo), (true, false, 1, 0));
+
+    make_commit(&repo, "local_file", "local commit");
+    assert_eq!(ahead_behind_remote(&repo), (true, true, 1, 1));
+}
+
+#[test]
+fn json_pre_prompt_serializes_all_fields() {
+    let precmd = PrePrompt {
+        path: String::from("~/some/dir"),
+        user_name: String::from("user"),
+        host: String::from("host"),
+        vcs_branch: String::from("master"),
+        vcs_state: String::from("rebasing"),
+        vcs_status_counts: StatusCounts {
+            staged: 1,
+            ..StatusCounts::default()
+        },
+        vcs_show_status_counts: false,
+        vcs_is_behind_remote: true,
+        vcs_is_ahead_of_remote: false,
+        vcs_behind_count: 2,
+        vcs_ahead_count: 0,
+        cmd_duration: Some(1500),
+        languages: vec![String::from("rust 1.0")],
+        config: Config::default(),
+        no_color: true,
+    };
+
+    let json = JsonPrePrompt::from(&precmd);
+
+    assert_eq!(json.path, "~/some/dir");
+    assert_eq!(json.branch, "master");
+    assert_eq!(json.state, "rebasing");
+    assert_eq!(json.status.staged, 1);
+    assert!(json.is_behind_remote);
+    assert!(!json.is_ahead_of_remote);
+    assert_eq!(json.behind_count, 2);
+    assert_eq!(json.cmd_duration_ms, Some(1500));
+    assert_eq!(json.languages, vec![String::from("rust 1.0")]);
+
+    let serialized = serde_json::to_string(&json).unwrap();
+    assert!(serialized.contains("\"branch\":\"master\""));
+    assert!(serialized.contains("\"staged\":1"));
+}
+
+fn make_commit(repo: &Repository, file_name: &str, msg: &str) {
+    let mut index = repo.index().unwrap();
+    let sig = repo.signature().unwrap();
+    let root = repo.path().parent().unwrap();
+    let head = repo.head().unwrap();
+    let target = head.target().unwrap();
+    let commit = repo.find_commit(target).unwrap();
+
+    File::create(&root.join(file_name)).unwrap();
+    index.add_path(Path::new(file_name)).unwrap();
+    index.write().unwrap();
+    let id = index.write_tree().unwrap();
+    let tree = repo.find_tree(id).unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &[&commit])
+        .unwrap();
 }
 
 fn temp_repo() -> (TempDir, Repository) {
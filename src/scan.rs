@@ -0,0 +1,171 @@
+use crate::precmd::{ahead_behind_remote, branch_name, status_counts};
+use clap::ArgMatches;
+use git2::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A one-line summary of a single repository's pending work.
+struct RepoReport {
+    path: PathBuf,
+    branch: String,
+    staged: bool,
+    unstaged: bool,
+    untracked: bool,
+    ahead: u32,
+    behind: u32,
+}
+
+impl RepoReport {
+    fn is_pending(&self, ignore_untracked: bool) -> bool {
+        self.staged
+            || self.unstaged
+            || (!ignore_untracked && self.untracked)
+            || self.ahead > 0
+            || self.behind > 0
+    }
+}
+
+/// Recursively finds every git repository under `root`, stopping the descent as soon as a
+/// `.git` directory is found so we don't walk into submodules or nested worktrees.
+fn find_repos(root: &Path) -> Vec<PathBuf> {
+    let mut repos = Vec::new();
+    visit(root, &mut repos);
+    repos
+}
+
+fn visit(dir: &Path, repos: &mut Vec<PathBuf>) {
+    if dir.join(".git").exists() {
+        repos.push(dir.to_path_buf());
+        return;
+    }
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            visit(&path, repos);
+        }
+    }
+}
+
+fn report_for(repo_path: &Path) -> Option<RepoReport> {
+    let mut repo = Repository::open(repo_path).ok()?;
+    let counts = status_counts(&mut repo);
+    let (_, _, behind, ahead) = ahead_behind_remote(&repo);
+
+    Some(RepoReport {
+        path: repo_path.to_path_buf(),
+        branch: branch_name(&repo).display_name(),
+        staged: counts.is_staged(),
+        unstaged: counts.is_unstaged(),
+        untracked: counts.is_untracked(),
+        ahead,
+        behind,
+    })
+}
+
+fn format_report(report: &RepoReport, ignore_untracked: bool) -> String {
+    let mut markers = Vec::new();
+    if report.staged {
+        markers.push("staged");
+    }
+    if report.unstaged {
+        markers.push("unstaged");
+    }
+    if !ignore_untracked && report.untracked {
+        markers.push("untracked");
+    }
+    if report.ahead > 0 {
+        markers.push("ahead");
+    }
+    if report.behind > 0 {
+        markers.push("behind");
+    }
+
+    if markers.is_empty() {
+        format!("{} [{}] clean", report.path.display(), report.branch)
+    } else {
+        format!(
+            "{} [{}] {}",
+            report.path.display(),
+            report.branch,
+            markers.join(", ")
+        )
+    }
+}
+
+/// Walks the directory tree under the given root and prints one line per discovered repository
+/// describing its pending work: uncommitted changes, untracked files, and commits ahead/behind
+/// its upstream.
+///
+/// This does not check for untagged/unpushed tags; see the `scan` subcommand's `--help` for why.
+crate fn render(sub_matchings: &ArgMatches<'_>) {
+    let root = sub_matchings.value_of("root").unwrap_or(".");
+    let pending_only = sub_matchings.is_present("pending-only");
+    let ignore_untracked = sub_matchings.is_present("ignore-untracked");
+
+    for repo_path in find_repos(Path::new(root)) {
+        let report = match report_for(&repo_path) {
+            Some(report) => report,
+            None => continue,
+        };
+
+        if pending_only && !report.is_pending(ignore_untracked) {
+            continue;
+        }
+
+        println!("{}", format_report(&report, ignore_untracked));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn find_repos_descends_into_subdirectories_without_walking_into_them() {
+        let root = TempDir::new("scan").unwrap();
+        Repository::init(root.path().join("one")).unwrap();
+        Repository::init(root.path().join("nested/two")).unwrap();
+        // Lives inside `one`, so the walk must stop at `one` and never find it.
+        Repository::init(root.path().join("one/inner")).unwrap();
+
+        let repos = find_repos(root.path());
+        assert_eq!(repos.len(), 2);
+    }
+
+    /// A repo with no commits yet (just `git init`'d) has an unborn HEAD. `report_for` must
+    /// handle it gracefully rather than panicking and aborting the whole scan.
+    #[test]
+    fn scan_does_not_panic_on_a_repo_with_no_commits() {
+        let root = TempDir::new("scan").unwrap();
+        Repository::init(root.path().join("unborn")).unwrap();
+        init_repo_with_commit(&root.path().join("committed"));
+
+        let reports: Vec<_> = find_repos(root.path())
+            .iter()
+            .filter_map(|path| report_for(path))
+            .collect();
+
+        assert_eq!(reports.len(), 2);
+    }
+
+    fn init_repo_with_commit(path: &Path) {
+        let repo = Repository::init(path).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "name").unwrap();
+        config.set_str("user.email", "email").unwrap();
+
+        let mut index = repo.index().unwrap();
+        let id = index.write_tree().unwrap();
+        let tree = repo.find_tree(id).unwrap();
+        let sig = repo.signature().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+    }
+}